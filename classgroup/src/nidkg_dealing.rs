@@ -6,6 +6,7 @@ use crate::polynomial::Polynomial;
 use crate::public_coefficients::PublicCoefficients;
 use crate::scalar_bls12381::field_add_assign;
 use crate::scalar_bls12381::field_mul;
+use crate::scalar_bls12381::field_sub_assign;
 use crate::utils::mpz_to_big;
 use anyhow::bail;
 use bicycl::b_i_c_y_c_l::CLHSMqk;
@@ -20,7 +21,7 @@ use std::str::FromStr;
 
 use serde::de::Error;
 
-const CG_DKG_STR: &str = "cgdkg";
+pub(crate) const CG_DKG_STR: &str = "cgdkg";
 
 use crate::utils::get_cl;
 
@@ -99,15 +100,255 @@ pub fn pubcoeff_to_pks(public_coefficients: &PublicCoefficients, total_nodes: us
     return pks;
 }
 
+// controls how node indices map onto evaluation points of the committee
+// commitment polynomial: sequential (i -> i+1) or evaluation-domain (i -> ω^i)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SharingMode {
+    Sequential,
+    EvaluationDomain,
+}
+
+// 2-adicity of the BLS12-381 scalar field: r-1 = 2^32 * t for odd t
+const BLS12_381_SCALAR_FIELD_TWO_ADICITY: usize = 32;
+
+// a fixed primitive 2^32-th root of unity in the BLS12-381 scalar field
+const BLS12_381_SCALAR_FIELD_TWO_ADIC_ROOT_OF_UNITY_HEX: &str =
+    "16a2a19edfe81f20d09b681922c813b4b63683508c2280b93829971f439f0d2b";
+
+// returns a primitive n-th root of unity, for n a power of two up to 2^32
+pub fn primitive_root_of_unity(n: usize) -> anyhow::Result<BIG> {
+    if n == 0 || !n.is_power_of_two() {
+        bail!("evaluation-domain sharing requires a power-of-two committee size");
+    }
+    let log_n = n.trailing_zeros() as usize;
+    if log_n > BLS12_381_SCALAR_FIELD_TWO_ADICITY {
+        bail!("committee size exceeds the field's 2-adicity");
+    }
+    let mut root = BIG::fromstring(BLS12_381_SCALAR_FIELD_TWO_ADIC_ROOT_OF_UNITY_HEX.to_string());
+    for _ in 0..(BLS12_381_SCALAR_FIELD_TWO_ADICITY - log_n) {
+        root = field_mul(&root, &root);
+    }
+    Ok(root)
+}
+
+/// In-place radix-2 number-theoretic transform over `ECP` data values with
+/// `BIG` scalar twiddle factors: each butterfly computes `u + ω^e·v` and
+/// `u - ω^e·v` where `u,v` are group elements and `ω^e·v` is a scalar-point
+/// multiply. `root` must be a primitive `data.len()`-th root of unity (or
+/// its inverse, to run the transform backwards).
+fn ecp_ntt(data: &mut Vec<ECP>, root: &BIG) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+
+    // Bit-reversal permutation.
+    let mut j = 0usize;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while bit & j != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let mut root_len = BIG::new_int(1);
+        for _ in 0..(n / len) {
+            root_len = field_mul(&root_len, root);
+        }
+        let mut start = 0;
+        while start < n {
+            let mut w = BIG::new_int(1);
+            for k in 0..half {
+                let u = data[start + k].clone();
+                let v = data[start + k + half].mul(&w);
+
+                let mut sum = u.clone();
+                sum.add(&v);
+                let mut diff = u;
+                diff.sub(&v);
+
+                data[start + k] = sum;
+                data[start + k + half] = diff;
+                w = field_mul(&w, &root_len);
+            }
+            start += len;
+        }
+        len *= 2;
+    }
+}
+
+/// Scalar-domain counterpart to [`ecp_ntt`], operating on field elements
+/// instead of group elements: called with `root` itself it is the forward
+/// transform (coefficients to evaluations, as [`poly_to_shares_evaluation_domain`]
+/// uses it to generate dealer-side shares); called with `root`'s inverse and
+/// the result scaled by `n^{-1}` it is the inverse transform (evaluations
+/// back to coefficients).
+pub fn scalar_ntt(data: &mut Vec<BIG>, root: &BIG) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+
+    let mut j = 0usize;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while bit & j != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let mut root_len = BIG::new_int(1);
+        for _ in 0..(n / len) {
+            root_len = field_mul(&root_len, root);
+        }
+        let mut start = 0;
+        while start < n {
+            let mut w = BIG::new_int(1);
+            for k in 0..half {
+                let u = data[start + k];
+                let v = field_mul(&data[start + k + half], &w);
+
+                let mut sum = u;
+                field_add_assign(&mut sum, &v);
+                let mut diff = u;
+                field_sub_assign(&mut diff, &v);
+
+                data[start + k] = sum;
+                data[start + k + half] = diff;
+                w = field_mul(&w, &root_len);
+            }
+            start += len;
+        }
+        len *= 2;
+    }
+}
+
+/// Evaluation-domain counterpart to [`pubcoeff_to_pks`]: evaluates the
+/// committee commitment polynomial at every `n`-th root of unity in a single
+/// `O(n log n)` NTT instead of `n` separate `O(t)` multi-scalar
+/// multiplications, at the cost of indexing node `i`'s partial public key by
+/// `ω^i` rather than `i+1`. `total_nodes` must be a power of two.
+pub fn pubcoeff_to_pks_evaluation_domain(
+    public_coefficients: &PublicCoefficients,
+    total_nodes: usize,
+) -> anyhow::Result<Vec<ECP>> {
+    let root = primitive_root_of_unity(total_nodes)?;
+
+    let mut data = public_coefficients.coefficients.clone();
+    if data.len() > total_nodes {
+        bail!("threshold exceeds the evaluation domain size");
+    }
+    let infinity = {
+        let mut p = ECP::new();
+        p.inf();
+        p
+    };
+    data.resize(total_nodes, infinity);
+
+    ecp_ntt(&mut data, &root);
+    Ok(data)
+}
+
+/// Dealer-side counterpart to [`pubcoeff_to_pks_evaluation_domain`]: evaluates
+/// `poly`'s coefficients (zero-padded to `total_nodes`) at every `n`-th root
+/// of unity via the same forward NTT, so share `k` lands on `ω^k`, matching
+/// the index receiver `k` is checked against in `verify_share`. `total_nodes`
+/// must be a power of two.
+pub fn poly_to_shares_evaluation_domain(
+    poly: &Polynomial,
+    total_nodes: usize,
+) -> anyhow::Result<Vec<BIG>> {
+    let root = primitive_root_of_unity(total_nodes)?;
+
+    let mut data = poly.coefficients.clone();
+    if data.len() > total_nodes {
+        bail!("threshold exceeds the evaluation domain size");
+    }
+    data.resize(total_nodes, BIG::new());
+
+    scalar_ntt(&mut data, &root);
+    Ok(data)
+}
+
+// per-dealer acknowledgement/complaint bookkeeping used to decide which
+// dealers in a dealing set are qualified
+#[derive(Clone, Debug, Default)]
+pub struct DkgTranscript {
+    acks: std::collections::HashMap<usize, std::collections::HashSet<usize>>,
+    upheld_complaints: std::collections::HashSet<usize>,
+}
+
+impl DkgTranscript {
+    pub fn new() -> Self {
+        DkgTranscript {
+            acks: std::collections::HashMap::new(),
+            upheld_complaints: std::collections::HashSet::new(),
+        }
+    }
+
+    // records that receiver_index verified its share from dealer_index
+    pub fn acknowledge(&mut self, dealer_index: usize, receiver_index: usize) {
+        self.acks
+            .entry(dealer_index)
+            .or_insert_with(std::collections::HashSet::new)
+            .insert(receiver_index);
+    }
+
+    // records that a complaint against dealer_index was upheld, permanently
+    // disqualifying it regardless of its acknowledgement count
+    pub fn uphold_complaint(&mut self, dealer_index: usize) {
+        self.upheld_complaints.insert(dealer_index);
+    }
+
+    // dealers with at least ack_threshold acknowledgements and no upheld complaint
+    pub fn qualified_dealers(&self, num_dealers: usize, ack_threshold: usize) -> Vec<usize> {
+        (0..num_dealers)
+            .filter(|dealer_index| {
+                !self.upheld_complaints.contains(dealer_index)
+                    && self
+                        .acks
+                        .get(dealer_index)
+                        .map_or(0, |acks| acks.len())
+                        >= ack_threshold
+            })
+            .collect()
+    }
+}
+
 // aggregates verified dealings to form node's partial secret key, committe public key,
-// partial public keys for all nodes and public coefficient.
+// partial public keys for all nodes and public coefficient. Only dealers that
+// `transcript` reports as qualified (see `DkgTranscript::qualified_dealers`)
+// contribute, so a single malicious or offline dealer cannot force the whole
+// protocol to `bail!`; the qualified dealer indices are returned alongside
+// the aggregated key material so the committee key is deterministic given
+// that set.
 pub fn aggregate_dealings(
     c: &CppBox<CLHSMqk>,
     dealings: &Vec<Dealing>,
+    transcript: &DkgTranscript,
+    ack_threshold: usize,
     cg_private_key: &SecretKeyBox,
     node_index: usize,
     total_nodes: usize,
-) -> anyhow::Result<(BIG, ECP, Vec<ECP>, PublicCoefficients)> {
+    mode: SharingMode,
+) -> anyhow::Result<(BIG, ECP, Vec<ECP>, PublicCoefficients, Vec<usize>)> {
+    let qualified_dealers = transcript.qualified_dealers(dealings.len(), ack_threshold);
+    if qualified_dealers.is_empty() {
+        bail!("no dealer reached the acknowledgement quorum");
+    }
+
     let mut accumulated_sk = BIG::new();
 
     let mut accumulated_public_polynomial = PublicCoefficients::from_poly_g(
@@ -115,7 +356,8 @@ pub fn aggregate_dealings(
         &get_cgdkg_zk_share_g(&CG_DKG_STR.to_string()),
     );
 
-    for dealing in dealings {
+    for &dealer_index in &qualified_dealers {
+        let dealing = &dealings[dealer_index];
         if accumulated_public_polynomial.coefficients.is_empty() {
             accumulated_public_polynomial = dealing.public_coefficients.clone();
         } else {
@@ -123,10 +365,14 @@ pub fn aggregate_dealings(
         }
     }
 
-    let my_shares: Result<Vec<BIG>, ()> = dealings
+    let my_shares: Result<Vec<BIG>, ()> = qualified_dealers
         .iter()
-        .map(|x| {
-            let mut dec = decrypt(&c, &cg_private_key, &x.ciphertexts[node_index]);
+        .map(|&dealer_index| {
+            let mut dec = decrypt(
+                &c,
+                &cg_private_key,
+                &dealings[dealer_index].ciphertexts[node_index],
+            );
 
             let dec_big = unsafe { mpz_to_big(dec.0.deref_mut()) };
 
@@ -145,12 +391,136 @@ pub fn aggregate_dealings(
         }
     }
 
-    let partial_pks = pubcoeff_to_pks(&accumulated_public_polynomial, total_nodes);
+    let partial_pks = match mode {
+        SharingMode::Sequential => pubcoeff_to_pks(&accumulated_public_polynomial, total_nodes),
+        SharingMode::EvaluationDomain => {
+            pubcoeff_to_pks_evaluation_domain(&accumulated_public_polynomial, total_nodes)?
+        }
+    };
 
     return Ok((
         accumulated_sk,
         accumulated_public_polynomial.coefficients[0].clone(),
         partial_pks,
         accumulated_public_polynomial,
+        qualified_dealers,
     ));
 }
+
+#[cfg(test)]
+mod dkg_transcript_tests {
+    use super::*;
+
+    #[test]
+    fn acknowledge_dedups_per_receiver() {
+        let mut transcript = DkgTranscript::new();
+        transcript.acknowledge(0, 1);
+        transcript.acknowledge(0, 1);
+        transcript.acknowledge(0, 2);
+
+        assert_eq!(transcript.qualified_dealers(1, 2), vec![0]);
+        assert_eq!(transcript.qualified_dealers(1, 3), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn uphold_complaint_disqualifies_regardless_of_ack_count() {
+        let mut transcript = DkgTranscript::new();
+        transcript.acknowledge(0, 1);
+        transcript.acknowledge(0, 2);
+        transcript.acknowledge(0, 3);
+        transcript.uphold_complaint(0);
+
+        assert_eq!(transcript.qualified_dealers(1, 1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn threshold_boundary_is_inclusive() {
+        let mut transcript = DkgTranscript::new();
+        transcript.acknowledge(0, 1);
+        transcript.acknowledge(0, 2);
+
+        assert_eq!(transcript.qualified_dealers(1, 2), vec![0]);
+        assert_eq!(transcript.qualified_dealers(1, 3), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn qualified_dealers_only_returns_requested_range() {
+        let mut transcript = DkgTranscript::new();
+        transcript.acknowledge(0, 1);
+        transcript.acknowledge(2, 1);
+        transcript.acknowledge(2, 2);
+        transcript.acknowledge(3, 1);
+
+        assert_eq!(transcript.qualified_dealers(3, 1), vec![0, 2]);
+    }
+}
+
+#[cfg(test)]
+mod evaluation_domain_tests {
+    use super::*;
+
+    // O(n*t) reference evaluator, mirroring `pubcoeff_to_pks`'s per-point
+    // approach but at the roots of unity instead of at `1..=n`.
+    fn naive_evaluate_at_roots_of_unity(
+        coefficients: &[ECP],
+        total_nodes: usize,
+        root: &BIG,
+    ) -> Vec<ECP> {
+        let mut values = Vec::new();
+        let mut root_pow = BIG::new_int(1);
+        for _ in 0..total_nodes {
+            let mut exponents = Vec::new();
+            let mut exponent = BIG::new_int(1);
+            for _ in 0..coefficients.len() {
+                exponents.push(exponent);
+                exponent = field_mul(&exponent, &root_pow);
+            }
+            values.push(ECP::muln(coefficients.len(), coefficients, exponents.as_slice()));
+            root_pow = field_mul(&root_pow, root);
+        }
+        values
+    }
+
+    #[test]
+    fn ntt_evaluation_matches_naive_evaluation_at_roots_of_unity() {
+        let total_nodes = 8usize;
+        let g = get_cgdkg_zk_share_g(&CG_DKG_STR.to_string());
+        let poly = Polynomial::random(3);
+        let public_coefficients = PublicCoefficients::from_poly_g(&poly, &g);
+
+        let root = primitive_root_of_unity(total_nodes).unwrap();
+        let expected =
+            naive_evaluate_at_roots_of_unity(&public_coefficients.coefficients, total_nodes, &root);
+
+        let actual = pubcoeff_to_pks_evaluation_domain(&public_coefficients, total_nodes).unwrap();
+
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!(a.equals(e));
+        }
+    }
+
+    // dealer-side counterpart of the above: poly_to_shares_evaluation_domain's
+    // shares must land on the same ω^k points pubcoeff_to_pks_evaluation_domain
+    // checks them against, i.e. share k must equal poly.evaluate_at(ω^k).
+    #[test]
+    fn ntt_shares_match_naive_evaluation_at_roots_of_unity() {
+        let total_nodes = 8usize;
+        let poly = Polynomial::random(3);
+
+        let root = primitive_root_of_unity(total_nodes).unwrap();
+        let mut root_pow = BIG::new_int(1);
+        let mut expected = Vec::new();
+        for _ in 0..total_nodes {
+            expected.push(poly.evaluate_at(&root_pow));
+            root_pow = field_mul(&root_pow, &root);
+        }
+
+        let actual = poly_to_shares_evaluation_domain(&poly, total_nodes).unwrap();
+
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert_eq!(BIG::comp(a, e), 0);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// proactive resharing of an already-aggregated committee secret to a new
+// membership (and possibly a new threshold), without ever reconstructing
+// the secret in one place
+
+use crate::cg_encryption::encrypt;
+use crate::nidkg_dealing::{
+    poly_to_shares_evaluation_domain, primitive_root_of_unity, Dealing, SharingMode, CG_DKG_STR,
+};
+use crate::nidkg_zk_share::{create_cgdkg_zk_share_proof, get_cgdkg_zk_share_g};
+use crate::polynomial::Polynomial;
+use crate::public_coefficients::PublicCoefficients;
+use crate::scalar_bls12381::{field_mul, field_sub_assign};
+use anyhow::bail;
+use bicycl::b_i_c_y_c_l::CLHSMqk;
+use bicycl::PublicKeyBox;
+use cpp_core::CppBox;
+use miracl_core_bls12381::bls12381::big::BIG;
+
+/// `r - 2`, where `r` is the order of the BLS12-381 scalar field. Used only
+/// as the exponent of a Fermat's-little-theorem inverse (`x^{-1} = x^{r-2}`);
+/// `field_mul`/`field_add_assign`/`field_sub_assign` already reduce modulo
+/// `r` internally.
+const BLS12_381_SCALAR_FIELD_ORDER_MINUS_TWO_HEX: &str =
+    "73eda753299d7d483339d80809a1d80553bda402fffe5bfefffffffeffffffff";
+
+fn field_inv(x: &BIG) -> BIG {
+    let mut exponent = BIG::fromstring(BLS12_381_SCALAR_FIELD_ORDER_MINUS_TWO_HEX.to_string());
+    let mut result = BIG::new_int(1);
+    let mut base = *x;
+    while BIG::comp(&exponent, &BIG::new()) != 0 {
+        if exponent.parity() == 1 {
+            result = field_mul(&result, &base);
+        }
+        base = field_mul(&base, &base);
+        exponent.fshr(1);
+    }
+    result
+}
+
+// base^exponent via square-and-multiply, exponent given as a plain usize
+// (the interpolation points below never exceed total_nodes)
+fn field_pow(base: &BIG, exponent: usize) -> BIG {
+    let mut result = BIG::new_int(1);
+    let mut b = *base;
+    let mut e = exponent;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = field_mul(&result, &b);
+        }
+        b = field_mul(&b, &b);
+        e >>= 1;
+    }
+    result
+}
+
+// the evaluation point old index k sits at, under mode's convention: k+1 for
+// Sequential, ω^k for EvaluationDomain (ω a total_nodes-th root of unity)
+fn interpolation_point(mode: SharingMode, root: Option<&BIG>, k: usize) -> BIG {
+    match mode {
+        SharingMode::Sequential => BIG::new_int((k + 1) as isize),
+        SharingMode::EvaluationDomain => field_pow(root.expect("root required for EvaluationDomain"), k),
+    }
+}
+
+/// The Lagrange coefficient of old index `i` at `x = 0`, over the set
+/// `resharing_participants` of old indices that are resharing their secret.
+/// `mode` and `total_nodes` must match the old committee's `aggregate_dealings`
+/// call, since they determine the evaluation point each index sits at: `i+1`
+/// for [`SharingMode::Sequential`], `ω^i` (the `total_nodes`-th root of unity
+/// to the `i`-th power) for [`SharingMode::EvaluationDomain`].
+pub fn lagrange_coefficient_at_zero(
+    resharing_participants: &[usize],
+    i: usize,
+    mode: SharingMode,
+    total_nodes: usize,
+) -> anyhow::Result<BIG> {
+    let root = match mode {
+        SharingMode::Sequential => None,
+        SharingMode::EvaluationDomain => Some(primitive_root_of_unity(total_nodes)?),
+    };
+    let x_i = interpolation_point(mode, root.as_ref(), i);
+    let mut numerator = BIG::new_int(1);
+    let mut denominator = BIG::new_int(1);
+
+    for &j in resharing_participants {
+        if j == i {
+            continue;
+        }
+        let x_j = interpolation_point(mode, root.as_ref(), j);
+
+        let mut neg_x_j = BIG::new();
+        field_sub_assign(&mut neg_x_j, &x_j);
+        numerator = field_mul(&numerator, &neg_x_j);
+
+        let mut diff = x_i;
+        field_sub_assign(&mut diff, &x_j);
+        denominator = field_mul(&denominator, &diff);
+    }
+
+    Ok(field_mul(&numerator, &field_inv(&denominator)))
+}
+
+/// Builds a fresh [`Dealing`] from `sk_i`, a partial secret of the *old*
+/// committee held by old index `i`. `old_mode` and `old_total_nodes` must
+/// match the old committee's `aggregate_dealings` call, so that `i`'s
+/// Lagrange weight is computed at the same evaluation point `sk_i` was
+/// originally shared at. The new polynomial's constant term is `sk_i`'s
+/// Lagrange-weighted contribution `λ_i · sk_i`, so once the dealings of every
+/// index in `resharing_participants` are aggregated by the new committee,
+/// the recovered secret equals the old
+/// committee secret, without any single resharing participant ever learning
+/// it. `new_threshold` is the new polynomial's degree `t'`, following the
+/// same convention as the rest of this series (e.g. `threshold_sign`'s
+/// `combine`): reconstructing the secret needs `new_threshold + 1` shares,
+/// not `new_threshold` of them. `new_mode` picks how the new committee's
+/// receivers are indexed: shares land at `receiver_index + 1` for
+/// [`SharingMode::Sequential`], or at `ω^receiver_index` (via
+/// [`poly_to_shares_evaluation_domain`]'s scalar forward-NTT twin of
+/// [`crate::nidkg_dealing::pubcoeff_to_pks_evaluation_domain`]) for
+/// [`SharingMode::EvaluationDomain`], so a new committee's `verify_share`
+/// checks the same points the dealer evaluated at.
+pub fn reshare_dealing(
+    c: &CppBox<CLHSMqk>,
+    resharing_participants: &[usize],
+    i: usize,
+    sk_i: &BIG,
+    old_mode: SharingMode,
+    old_total_nodes: usize,
+    new_threshold: usize,
+    new_mode: SharingMode,
+    new_receiver_public_keys: &[PublicKeyBox],
+) -> anyhow::Result<Dealing> {
+    if new_threshold + 1 > new_receiver_public_keys.len() {
+        bail!("invalid threshold for the resharing committee");
+    }
+
+    let lambda_i =
+        lagrange_coefficient_at_zero(resharing_participants, i, old_mode, old_total_nodes)?;
+    let weighted_contribution = field_mul(&lambda_i, sk_i);
+
+    let mut poly = Polynomial::random(new_threshold);
+    poly.coefficients[0] = weighted_contribution;
+
+    let public_coefficients =
+        PublicCoefficients::from_poly_g(&poly, &get_cgdkg_zk_share_g(&CG_DKG_STR.to_string()));
+
+    let shares = match new_mode {
+        SharingMode::Sequential => (0..new_receiver_public_keys.len())
+            .map(|receiver_index| poly.evaluate_at(&BIG::new_int((receiver_index + 1) as isize)))
+            .collect::<Vec<BIG>>(),
+        SharingMode::EvaluationDomain => {
+            poly_to_shares_evaluation_domain(&poly, new_receiver_public_keys.len())?
+        }
+    };
+
+    let ciphertexts = new_receiver_public_keys
+        .iter()
+        .zip(shares.iter())
+        .map(|(pk, share)| encrypt(c, pk, share))
+        .collect();
+
+    let zk_proof_correct_sharing =
+        create_cgdkg_zk_share_proof(c, &poly, &public_coefficients, new_receiver_public_keys);
+
+    Ok(Dealing {
+        public_coefficients,
+        ciphertexts,
+        zk_proof_correct_sharing,
+    })
+}
+
+/// Aggregates reshared dealings produced by [`reshare_dealing`] into the new
+/// committee's public coefficients, asserting that `public_coefficients[0]`
+/// (the new committee public key) equals `old_committee_public_key`, so that
+/// operators get a verifiable check that the key was preserved across the
+/// membership change.
+pub fn aggregate_reshared_dealings(
+    reshared_dealings: &[Dealing],
+    old_committee_public_key: &miracl_core_bls12381::bls12381::ecp::ECP,
+) -> anyhow::Result<PublicCoefficients> {
+    if reshared_dealings.is_empty() {
+        bail!("no reshared dealings to aggregate");
+    }
+
+    let mut accumulated = reshared_dealings[0].public_coefficients.clone();
+    for dealing in &reshared_dealings[1..] {
+        accumulated += dealing.public_coefficients.clone();
+    }
+
+    if !accumulated.coefficients[0].equals(old_committee_public_key) {
+        bail!("resharing did not preserve the committee public key");
+    }
+
+    Ok(accumulated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nidkg_complaint::verify_share;
+    use bicycl::SecretKeyBox;
+
+    // End-to-end check that EvaluationDomain mode actually works on the
+    // dealer side: a reshared Dealing's shares must land where verify_share
+    // checks them, i.e. at ω^receiver_index rather than receiver_index + 1.
+    #[test]
+    fn evaluation_domain_reshare_round_trips_through_verify_share() {
+        let c = crate::utils::get_cl();
+        let total_nodes = 4usize; // power of two, required by EvaluationDomain
+
+        let keypairs: Vec<(SecretKeyBox, PublicKeyBox)> =
+            (0..total_nodes).map(|_| unsafe { c.keygen() }).collect();
+        let receiver_pks: Vec<PublicKeyBox> =
+            keypairs.iter().map(|(_, pk)| pk.clone()).collect();
+
+        let resharing_participants = [0usize];
+        let sk_i = BIG::new_int(123456789);
+
+        let dealing = reshare_dealing(
+            &c,
+            &resharing_participants,
+            0,
+            &sk_i,
+            SharingMode::Sequential,
+            1,
+            3,
+            SharingMode::EvaluationDomain,
+            &receiver_pks,
+        )
+        .unwrap();
+
+        for (node_index, (sk, _)) in keypairs.iter().enumerate() {
+            assert!(verify_share(
+                &c,
+                sk,
+                &dealing,
+                node_index,
+                total_nodes,
+                SharingMode::EvaluationDomain,
+            )
+            .is_ok());
+        }
+    }
+}
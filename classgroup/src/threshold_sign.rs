@@ -0,0 +1,122 @@
+// threshold BLS signing/verification over the key material aggregate_dealings
+// produces: partial secret keys, the committee public key, and partial public keys
+
+use crate::nidkg_dealing::{SharingMode, CG_DKG_STR};
+use crate::nidkg_reshare::lagrange_coefficient_at_zero;
+use crate::nidkg_zk_share::get_cgdkg_zk_share_g;
+use anyhow::bail;
+use miracl_core_bls12381::bls12381::big::BIG;
+use miracl_core_bls12381::bls12381::ecp::ECP;
+use miracl_core_bls12381::bls12381::ecp2::ECP2;
+use miracl_core_bls12381::bls12381::pair;
+use sha2::{Digest, Sha256};
+
+/// A single node's contribution to a threshold BLS signature over a
+/// message, produced by [`sign_share`].
+#[derive(Clone, Debug)]
+pub struct SignatureShare(pub ECP2);
+
+/// A threshold BLS signature, recovered by [`combine`] from `t+1` valid
+/// shares.
+#[derive(Clone, Debug)]
+pub struct Signature(pub ECP2);
+
+fn hash_to_g2(msg: &[u8]) -> ECP2 {
+    let mut hasher = Sha256::new();
+    hasher.update(b"cgdkg-threshold-sign");
+    hasher.update(msg);
+    ECP2::mapit(&hasher.finalize())
+}
+
+/// Hashes `msg` to a G2 point and multiplies it by `sk_i`.
+pub fn sign_share(sk_i: &BIG, msg: &[u8]) -> SignatureShare {
+    SignatureShare(hash_to_g2(msg).mul(sk_i))
+}
+
+fn pairings_match(sig: &ECP2, pk: &ECP, msg: &[u8]) -> bool {
+    let h = hash_to_g2(msg);
+    let g1 = get_cgdkg_zk_share_g(&CG_DKG_STR.to_string());
+
+    let lhs = pair::fexp(&pair::ate(&h, pk));
+    let rhs = pair::fexp(&pair::ate(sig, &g1));
+    lhs.equals(&rhs)
+}
+
+/// Checks `share` against node `i`'s partial public key `pk_i` (as returned
+/// by `pubcoeff_to_pks`) and `msg`.
+pub fn verify_share(share: &SignatureShare, pk_i: &ECP, msg: &[u8]) -> bool {
+    pairings_match(&share.0, pk_i, msg)
+}
+
+/// Interpolates `sigma = sum(lambda_j * share_j)` over `t+1` valid shares at
+/// the given `indices`, with Lagrange coefficients `lambda_j` computed at 0
+/// over that set, producing a signature verifiable with the committee
+/// public key via [`verify`]. `mode` and `total_nodes` must match the
+/// `aggregate_dealings` call that produced the committee's key material, since
+/// they determine the evaluation point each index's share sits at.
+pub fn combine(
+    shares: &[SignatureShare],
+    indices: &[usize],
+    mode: SharingMode,
+    total_nodes: usize,
+) -> anyhow::Result<Signature> {
+    if shares.is_empty() || shares.len() != indices.len() {
+        bail!("need a non-empty, matching set of shares and indices to combine");
+    }
+
+    let mut combined = ECP2::new();
+    combined.inf();
+    for (share, &i) in shares.iter().zip(indices.iter()) {
+        let lambda_i = lagrange_coefficient_at_zero(indices, i, mode, total_nodes)?;
+        combined.add(&share.0.mul(&lambda_i));
+    }
+
+    Ok(Signature(combined))
+}
+
+/// Checks a combined [`Signature`] against the committee public key and
+/// `msg`.
+pub fn verify(sig: &Signature, committee_pk: &ECP, msg: &[u8]) -> bool {
+    pairings_match(&sig.0, committee_pk, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nidkg_dealing::pubcoeff_to_pks;
+    use crate::polynomial::Polynomial;
+    use crate::public_coefficients::PublicCoefficients;
+
+    #[test]
+    fn combined_signature_verifies_and_wrong_share_set_fails() {
+        let threshold = 2; // t = 2, so t+1 = 3 shares reconstruct
+        let total_nodes = 5;
+
+        let g = get_cgdkg_zk_share_g(&CG_DKG_STR.to_string());
+        let poly = Polynomial::random(threshold);
+        let committee_pk = g.mul(&poly.coefficients[0]);
+        let public_coefficients = PublicCoefficients::from_poly_g(&poly, &g);
+        let pks = pubcoeff_to_pks(&public_coefficients, total_nodes);
+
+        let sks: Vec<BIG> = (1..=total_nodes)
+            .map(|x| poly.evaluate_at(&BIG::new_int(x as isize)))
+            .collect();
+
+        let msg = b"threshold signing test message";
+
+        let indices = vec![0usize, 2, 4];
+        let shares: Vec<SignatureShare> =
+            indices.iter().map(|&i| sign_share(&sks[i], msg)).collect();
+        for (&i, share) in indices.iter().zip(shares.iter()) {
+            assert!(verify_share(share, &pks[i], msg));
+        }
+
+        let combined = combine(&shares, &indices, SharingMode::Sequential, total_nodes).unwrap();
+        assert!(verify(&combined, &committee_pk, msg));
+
+        // Fewer than t+1 shares interpolate to the wrong secret.
+        let wrong_combined =
+            combine(&shares[..2], &indices[..2], SharingMode::Sequential, total_nodes).unwrap();
+        assert!(!verify(&wrong_combined, &committee_pk, msg));
+    }
+}